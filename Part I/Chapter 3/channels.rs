@@ -0,0 +1,57 @@
+/* A Channels Subsystem over crossbeam-channel */
+
+// The message-passing snippets in this chunk all use `std::sync::mpsc`, which
+// is multi-producer *single*-consumer, unbounded, and cannot wait on more than
+// one channel at a time. For low-latency work those limits bite: an unbounded
+// queue lets a fast producer grow memory (and latency) without bound, and the
+// single-consumer restriction rules out a worker pool draining one queue.
+//
+// This module wraps `crossbeam-channel` to demonstrate the three capabilities
+// the std channel lacks:
+//
+//   * true **MPMC** — both ends clone, so many producers *and* many consumers
+//     share one channel;
+//   * **bounded** channels that apply backpressure — a full `bounded(cap)`
+//     blocks the sender, which is how a low-latency system keeps its queues
+//     (and tail latency) from growing without limit;
+//   * **`select`** — wait on several channels plus a timeout at once, so a
+//     worker can drain a work queue while still honouring a shutdown signal.
+//
+// The ported examples live in `channels_examples.rs` and the bounded-vs-
+// unbounded benchmark in `channels_bench.rs`.
+
+use std::time::Duration;
+
+pub use crossbeam_channel::{bounded, unbounded, Receiver, RecvError, Sender};
+
+/// The outcome of draining a work channel while watching a shutdown signal.
+pub enum Drained<T> {
+  /// A work item was received.
+  Work(T),
+  /// The shutdown channel fired (or closed); the worker should stop.
+  Shutdown,
+  /// Neither channel produced anything within the timeout.
+  Idle,
+}
+
+/// Wait on a work channel and a shutdown channel simultaneously, giving up
+/// after `timeout`.
+///
+/// This is the `select!`-style multiplexing the std channel cannot express: a
+/// worker loop calls it to pull the next job while remaining immediately
+/// responsive to a shutdown request, without busy-polling either channel.
+pub fn recv_or_shutdown<T>(
+  work: &Receiver<T>,
+  shutdown: &Receiver<()>,
+  timeout: Duration,
+) -> Drained<T> {
+  crossbeam_channel::select! {
+    recv(work) -> msg => match msg {
+      Ok(item) => Drained::Work(item),
+      // A closed work channel means no more work will ever arrive.
+      Err(_) => Drained::Shutdown,
+    },
+    recv(shutdown) -> _ => Drained::Shutdown,
+    default(timeout) => Drained::Idle,
+  }
+}
@@ -0,0 +1,51 @@
+/* Bounded vs. Unbounded: Backpressure Bounds Memory and Tail Latency */
+
+// A fast producer feeding a slow consumer. With an unbounded channel the queue
+// grows to hold every outstanding message — unbounded memory, and each message
+// waits behind the whole backlog (a latency tail that grows with the run). A
+// bounded channel caps the in-flight count: the producer blocks instead of
+// enqueuing, so memory is bounded and per-message latency stays flat.
+//
+// We report the peak queue depth and the p99 end-to-end latency for each.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::channels::{bounded, unbounded, Receiver, Sender};
+
+const MESSAGES: usize = 20_000;
+/// Per-message consumer cost; the producer has no such delay, so it races
+/// ahead and exposes the queueing behaviour.
+const CONSUME: Duration = Duration::from_micros(50);
+
+/// Drive `MESSAGES` through a channel and return (peak depth, p99 latency).
+fn run(tx: Sender<Instant>, rx: Receiver<Instant>) -> (usize, Duration) {
+  let consumer = thread::spawn(move || {
+    let mut peak = 0;
+    let mut latencies = Vec::with_capacity(MESSAGES);
+    for sent in rx.iter() {
+      peak = peak.max(rx.len());
+      latencies.push(sent.elapsed());
+      thread::sleep(CONSUME);
+    }
+    latencies.sort_unstable();
+    (peak, latencies[(latencies.len() as f64 * 0.99) as usize])
+  });
+
+  for _ in 0..MESSAGES {
+    tx.send(Instant::now()).unwrap();
+  }
+  drop(tx);
+  consumer.join().unwrap()
+}
+
+fn main() {
+  let (utx, urx) = unbounded();
+  let (unbounded_peak, unbounded_p99) = run(utx, urx);
+
+  let (btx, brx) = bounded(64);
+  let (bounded_peak, bounded_p99) = run(btx, brx);
+
+  println!("unbounded: peak depth {unbounded_peak:>7}, p99 latency {unbounded_p99:?}");
+  println!("bounded:   peak depth {bounded_peak:>7}, p99 latency {bounded_p99:?}");
+}
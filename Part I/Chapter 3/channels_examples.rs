@@ -0,0 +1,101 @@
+/* Message Passing with crossbeam-channel */
+
+// The `std::sync::mpsc` "Multiple Producers" and message-passing examples from
+// earlier in the chunk, ported onto the `channels` subsystem to show MPMC,
+// bounded backpressure, and `select`-based shutdown.
+
+use std::thread;
+use std::time::Duration;
+
+use crate::channels::{bounded, recv_or_shutdown, unbounded, Drained};
+
+/* Multiple Producers and Multiple Consumers */
+
+// The std version had one consumer draining `rx`. With crossbeam both ends
+// clone, so several consumers share the work too.
+fn multiple_producers_multiple_consumers() {
+  let (tx, rx) = unbounded();
+
+  let producers: Vec<_> = (0..5)
+    .map(|i| {
+      let tx = tx.clone();
+      thread::spawn(move || {
+        tx.send(format!("Message {i}")).unwrap();
+      })
+    })
+    .collect();
+  drop(tx); // Close the original sender so consumers see the channel end.
+
+  let consumers: Vec<_> = (0..3)
+    .map(|c| {
+      let rx = rx.clone();
+      thread::spawn(move || {
+        for received in rx.iter() {
+          println!("Consumer {c} received: {received}");
+        }
+      })
+    })
+    .collect();
+
+  for p in producers {
+    p.join().unwrap();
+  }
+  for c in consumers {
+    c.join().unwrap();
+  }
+}
+
+/* Bounded Channels for Backpressure */
+
+// A `bounded(cap)` channel blocks the sender once it holds `cap` messages, so a
+// fast producer cannot outrun a slow consumer and grow memory without bound.
+fn bounded_backpressure() {
+  let (tx, rx) = bounded(4);
+
+  let consumer = thread::spawn(move || {
+    for received in rx.iter() {
+      // Deliberately slow: the producer blocks on `send` while we catch up.
+      thread::sleep(Duration::from_millis(10));
+      println!("Consumed: {received}");
+    }
+  });
+
+  for i in 0..16 {
+    // Blocks whenever the channel is full — the backpressure the std
+    // unbounded channel never applies.
+    tx.send(i).unwrap();
+  }
+  drop(tx);
+  consumer.join().unwrap();
+}
+
+/* Draining Work While Honouring a Shutdown Signal */
+
+fn worker_with_shutdown() {
+  let (work_tx, work_rx) = unbounded::<u64>();
+  let (shutdown_tx, shutdown_rx) = bounded::<()>(1);
+
+  let worker = thread::spawn(move || loop {
+    match recv_or_shutdown(&work_rx, &shutdown_rx, Duration::from_millis(100)) {
+      Drained::Work(job) => println!("Handling job {job}"),
+      Drained::Shutdown => {
+        println!("Shutting down");
+        break;
+      }
+      Drained::Idle => println!("Idle tick; still waiting for work"),
+    }
+  });
+
+  for job in 0..5 {
+    work_tx.send(job).unwrap();
+  }
+  thread::sleep(Duration::from_millis(250));
+  shutdown_tx.send(()).unwrap();
+  worker.join().unwrap();
+}
+
+fn main() {
+  multiple_producers_multiple_consumers();
+  bounded_backpressure();
+  worker_with_shutdown();
+}
@@ -0,0 +1,28 @@
+/* Building an Asynchronous Server (without Tokio) */
+
+// The chunk first shows a thread-per-connection `std::net::TcpListener` server
+// and then rewrites it with `tokio`. Here is the same echo server ported onto
+// the home-grown runtime instead: `executor::block_on`/`spawn` drive the
+// tasks, and `reactor::Async` makes the std sockets awaitable. No Tokio.
+
+use crate::executor::{block_on, spawn};
+use crate::reactor::Async;
+
+fn main() {
+  block_on(async {
+    let listener = Async::bind("127.0.0.1:8080").unwrap();
+    loop {
+      let (stream, _) = listener.accept().await.unwrap();
+      // One task per connection, exactly like the `tokio::spawn` version, but
+      // multiplexed onto the single reactor thread.
+      spawn(async move {
+        let mut buffer = [0; 1024];
+        stream.read(&mut buffer).await.unwrap();
+        stream
+          .write_all(b"HTTP/1.1 200 OK\r\n\r\nHello, async world!")
+          .await
+          .unwrap();
+      });
+    }
+  });
+}
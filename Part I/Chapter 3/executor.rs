@@ -0,0 +1,239 @@
+/* A From-Scratch Single-Threaded Async Executor */
+
+// The "Building an Asynchronous Server" snippet reaches for `tokio` to get
+// `block_on`/`spawn`, but nothing about driving a `Future` to completion is
+// magic. This module is a minimal, self-contained runtime in the spirit of
+// `smol`: it shows how the `Future::poll` signature shown in the chunk is
+// actually exercised. There are three moving parts:
+//
+//   1. a *task* — a `Pin<Box<dyn Future<Output = ()>>>` kept on a run queue;
+//   2. a hand-built `Waker` (via `RawWaker`/`RawWakerVTable`) whose `wake`
+//      pushes the task back onto the ready queue and unparks the run loop;
+//   3. a *run loop* that pops a ready task, builds a `Context` from its waker,
+//      calls `poll`, and either drops the task (`Poll::Ready`) or leaves it
+//      parked until its waker fires again (`Poll::Pending`).
+//
+// `block_on` drives a single future to completion, running any tasks spawned
+// with `spawn` along the way, and parks the current thread on a `Condvar`
+// whenever there is no ready work.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+/// A unit of work owned by the executor: a heap-allocated, pinned future that
+/// resolves to `()`. Spawned futures are adapted to this shape.
+type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// State shared between the run loop and every task's waker.
+///
+/// A single `Mutex` guards the ready queue *and* the `woken` flag so the
+/// `Condvar` has exactly one companion lock, which keeps the park/unpark
+/// handshake free of lost-wakeup races.
+struct Shared {
+  inner: Mutex<Inner>,
+  signal: Condvar,
+}
+
+struct Inner {
+  /// Tasks that are ready to make progress, in FIFO order.
+  ready: VecDeque<Arc<Task>>,
+  /// Set by any waker; consumed by the run loop before it parks.
+  woken: bool,
+}
+
+/// A spawned task: its future plus a handle back to the shared queue so that
+/// waking it can reschedule it.
+struct Task {
+  future: Mutex<Option<BoxFuture>>,
+  shared: Arc<Shared>,
+}
+
+impl Task {
+  /// Re-enqueue this task and wake the run loop. Called from the waker, which
+  /// may run on another thread (e.g. the reactor thread).
+  fn schedule(self: &Arc<Self>) {
+    let mut inner = self.shared.inner.lock().unwrap();
+    inner.ready.push_back(self.clone());
+    inner.woken = true;
+    self.shared.signal.notify_one();
+  }
+
+  /// Poll the task once with its own waker. On `Poll::Pending` the future is
+  /// put back for the next wakeup; on `Poll::Ready` it is dropped.
+  fn run(self: Arc<Self>) {
+    let mut slot = self.future.lock().unwrap();
+    let Some(mut future) = slot.take() else {
+      return; // Already completed; a spurious reschedule.
+    };
+    let waker = task_waker(self.clone());
+    let mut cx = Context::from_waker(&waker);
+    if future.as_mut().poll(&mut cx).is_pending() {
+      *slot = Some(future);
+    }
+  }
+}
+
+thread_local! {
+  /// The runtime installed for the duration of the enclosing `block_on`, so
+  /// that `spawn` can find the run queue without threading it through.
+  static CURRENT: RefCell<Option<Arc<Shared>>> = const { RefCell::new(None) };
+}
+
+/// Spawn a future onto the current executor. It runs concurrently with the
+/// future passed to `block_on` and is driven to completion by the same loop.
+///
+/// # Panics
+///
+/// Panics if called outside of a `block_on` context.
+pub fn spawn<F>(future: F)
+where
+  F: Future<Output = ()> + Send + 'static,
+{
+  let shared = CURRENT.with(|c| {
+    c.borrow()
+      .as_ref()
+      .expect("`spawn` called outside of `block_on`")
+      .clone()
+  });
+  let task = Arc::new(Task {
+    future: Mutex::new(Some(Box::pin(future))),
+    shared: shared.clone(),
+  });
+  task.schedule();
+}
+
+/// Drive `future` to completion on the current thread, running any spawned
+/// tasks in between and parking whenever there is nothing ready to poll.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+  let shared = Arc::new(Shared {
+    inner: Mutex::new(Inner {
+      ready: VecDeque::new(),
+      woken: false,
+    }),
+    signal: Condvar::new(),
+  });
+
+  // Install the runtime so `spawn` works for the duration of the call, and
+  // restore the previous one on the way out (nested `block_on` is allowed).
+  let previous = CURRENT.with(|c| c.replace(Some(shared.clone())));
+  let _guard = CurrentGuard { previous };
+
+  // The main future is driven in place (no `'static`/`Send` bound) using a
+  // waker that merely unparks the run loop.
+  let main_waker = thread_waker(shared.clone());
+  let mut cx = Context::from_waker(&main_waker);
+  let mut future = std::pin::pin!(future);
+
+  loop {
+    if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+      return output;
+    }
+
+    // Run every task that is currently ready. A task may itself wake the
+    // main future, which we detect via the `woken` flag below.
+    loop {
+      let next = shared.inner.lock().unwrap().ready.pop_front();
+      match next {
+        Some(task) => task.run(),
+        None => break,
+      }
+    }
+
+    // Park until some waker fires. Re-polling the main future is cheap, so we
+    // loop back to the top rather than tracking its readiness separately.
+    let mut inner = shared.inner.lock().unwrap();
+    while !inner.woken && inner.ready.is_empty() {
+      inner = shared.signal.wait(inner).unwrap();
+    }
+    inner.woken = false;
+  }
+}
+
+/// Restores the previously-installed runtime when `block_on` returns, even on
+/// an unwinding panic.
+struct CurrentGuard {
+  previous: Option<Arc<Shared>>,
+}
+
+impl Drop for CurrentGuard {
+  fn drop(&mut self) {
+    CURRENT.with(|c| *c.borrow_mut() = self.previous.take());
+  }
+}
+
+// --- Hand-built wakers --------------------------------------------------------
+//
+// A `Waker` is a type-erased `(*const (), &'static RawWakerVTable)` pair. We
+// build two flavours, each over an `Arc`: task wakers reschedule a `Task`,
+// thread wakers simply flip `woken` and notify the `Condvar`. In both cases
+// `clone`/`drop` manage the `Arc`'s refcount by hand.
+
+/// Build a waker for a spawned task.
+fn task_waker(task: Arc<Task>) -> Waker {
+  unsafe { Waker::from_raw(task_raw_waker(task)) }
+}
+
+fn task_raw_waker(task: Arc<Task>) -> RawWaker {
+  RawWaker::new(Arc::into_raw(task) as *const (), &TASK_VTABLE)
+}
+
+static TASK_VTABLE: RawWakerVTable = RawWakerVTable::new(
+  |ptr| unsafe {
+    let task = Arc::from_raw(ptr as *const Task);
+    let clone = task.clone();
+    std::mem::forget(task); // Keep the original refcount owned by the waker.
+    task_raw_waker(clone)
+  },
+  |ptr| unsafe {
+    let task = Arc::from_raw(ptr as *const Task);
+    task.schedule(); // Consumes the refcount owned by this waker.
+  },
+  |ptr| unsafe {
+    let task = Arc::from_raw(ptr as *const Task);
+    task.schedule();
+    std::mem::forget(task); // `wake_by_ref` must not consume the waker.
+  },
+  |ptr| unsafe {
+    drop(Arc::from_raw(ptr as *const Task));
+  },
+);
+
+/// Build a waker that just unparks the run loop (used for the main future).
+fn thread_waker(shared: Arc<Shared>) -> Waker {
+  unsafe { Waker::from_raw(thread_raw_waker(shared)) }
+}
+
+fn thread_raw_waker(shared: Arc<Shared>) -> RawWaker {
+  RawWaker::new(Arc::into_raw(shared) as *const (), &THREAD_VTABLE)
+}
+
+fn notify(shared: &Shared) {
+  let mut inner = shared.inner.lock().unwrap();
+  inner.woken = true;
+  shared.signal.notify_one();
+}
+
+static THREAD_VTABLE: RawWakerVTable = RawWakerVTable::new(
+  |ptr| unsafe {
+    let shared = Arc::from_raw(ptr as *const Shared);
+    let clone = shared.clone();
+    std::mem::forget(shared);
+    thread_raw_waker(clone)
+  },
+  |ptr| unsafe {
+    let shared = Arc::from_raw(ptr as *const Shared);
+    notify(&shared);
+  },
+  |ptr| unsafe {
+    let shared = Arc::from_raw(ptr as *const Shared);
+    notify(&shared);
+    std::mem::forget(shared);
+  },
+  |ptr| unsafe {
+    drop(Arc::from_raw(ptr as *const Shared));
+  },
+);
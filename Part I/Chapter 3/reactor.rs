@@ -0,0 +1,312 @@
+/* A Reactor and `Async<T>` I/O Adapter */
+
+// The "Building an Asynchronous Server" snippet uses `tokio::net::TcpListener`
+// to get non-blocking accept/read/write. This module provides the same
+// capability from first principles, in the spirit of `async-io`: a single
+// reactor thread multiplexes every registered file descriptor through one
+// epoll/kqueue instance (via the `polling` crate), and `Async<T>` turns any
+// `T: AsRawFd` into something awaitable.
+//
+// The flow is:
+//
+//   * `Async::new` sets the fd non-blocking and registers it with the global
+//     `Reactor`, which records a `Source` (fd + key + parked read/write
+//     wakers) in a `Slab`.
+//   * `read_with`/`write_with` attempt the syscall; on `WouldBlock` they stash
+//     the current task's waker in the `Source` and return `Poll::Pending`.
+//   * The reactor thread loops on `Poller::wait`; for every ready fd it wakes
+//     the stored wakers so the executor re-polls the corresponding task.
+//
+// Paired with the `executor` module, the echo server in `echo_server.rs` runs
+// with no Tokio at all.
+
+use std::future::Future;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::os::fd::{AsRawFd, BorrowedFd, RawFd};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use polling::{Event, Events, Poller};
+use slab::Slab;
+
+/// Per-fd state held by the reactor: the raw descriptor plus the wakers of the
+/// tasks currently blocked waiting to read from or write to it.
+struct Source {
+  raw: RawFd,
+  key: usize,
+  readers: Vec<Waker>,
+  writers: Vec<Waker>,
+}
+
+impl Source {
+  /// The set of events we are currently interested in, derived from whether
+  /// any task is parked on a read or a write. `polling` is oneshot, so this is
+  /// re-armed every time a waker is (re)registered.
+  fn interest(&self) -> Event {
+    let mut event = Event::none(self.key);
+    event.readable = !self.readers.is_empty();
+    event.writable = !self.writers.is_empty();
+    event
+  }
+}
+
+/// The global I/O reactor: one `Poller` plus a registry of sources, driven by a
+/// dedicated background thread.
+struct Reactor {
+  poller: Poller,
+  sources: Mutex<Slab<Arc<Mutex<Source>>>>,
+}
+
+impl Reactor {
+  /// Return the process-wide reactor, starting its background thread the first
+  /// time it is touched.
+  fn get() -> &'static Reactor {
+    static REACTOR: OnceLock<Reactor> = OnceLock::new();
+    let reactor = REACTOR.get_or_init(|| Reactor {
+      poller: Poller::new().expect("failed to create I/O poller"),
+      sources: Mutex::new(Slab::new()),
+    });
+    static STARTED: OnceLock<()> = OnceLock::new();
+    STARTED.get_or_init(|| {
+      thread::Builder::new()
+        .name("reactor".to_string())
+        .spawn(|| reactor.run())
+        .expect("failed to spawn reactor thread");
+    });
+    reactor
+  }
+
+  /// Register `raw` with the poller and return its source handle.
+  fn register(&self, raw: RawFd) -> Arc<Mutex<Source>> {
+    let mut sources = self.sources.lock().unwrap();
+    let entry = sources.vacant_entry();
+    let key = entry.key();
+    let source = Arc::new(Mutex::new(Source {
+      raw,
+      key,
+      readers: Vec::new(),
+      writers: Vec::new(),
+    }));
+    // SAFETY: the fd stays registered until `deregister` removes it in
+    // `Async::drop`, before the descriptor itself is closed.
+    unsafe {
+      self
+        .poller
+        .add(raw, Event::none(key))
+        .expect("failed to register fd with poller");
+    }
+    entry.insert(source.clone());
+    source
+  }
+
+  /// Remove a source from the poller and registry.
+  fn deregister(&self, source: &Arc<Mutex<Source>>) {
+    let (raw, key) = {
+      let source = source.lock().unwrap();
+      (source.raw, source.key)
+    };
+    self.sources.lock().unwrap().try_remove(key);
+    // SAFETY: `raw` is still open; `Async::drop` deregisters before closing.
+    let _ = self.poller.delete(unsafe { BorrowedFd::borrow_raw(raw) });
+  }
+
+  /// Re-arm the poller for `source` based on its current parked wakers.
+  fn rearm(&self, source: &Mutex<Source>) {
+    let source = source.lock().unwrap();
+    // SAFETY: `raw` remains valid while the owning `Async` is alive.
+    let _ = self
+      .poller
+      .modify(unsafe { BorrowedFd::borrow_raw(source.raw) }, source.interest());
+  }
+
+  /// The reactor loop: wait for readiness, then wake every task parked on a
+  /// ready fd. Runs forever on the reactor thread.
+  fn run(&self) -> ! {
+    let mut events = Events::new();
+    loop {
+      events.clear();
+      if self.poller.wait(&mut events, None).is_err() {
+        continue;
+      }
+      for event in events.iter() {
+        let source = self.sources.lock().unwrap().get(event.key).cloned();
+        let Some(source) = source else { continue };
+        let mut source = source.lock().unwrap();
+        if event.readable {
+          source.readers.drain(..).for_each(Waker::wake);
+        }
+        if event.writable {
+          source.writers.drain(..).for_each(Waker::wake);
+        }
+      }
+    }
+  }
+}
+
+/// An adapter that makes a std I/O type awaitable by driving it through the
+/// global [`Reactor`]. Wraps any `T: AsRawFd`, sets the fd non-blocking on
+/// construction, and deregisters it on drop.
+pub struct Async<T: AsRawFd> {
+  source: Arc<Mutex<Source>>,
+  io: Option<T>,
+}
+
+impl<T: AsRawFd> Async<T> {
+  /// Wrap `io`, putting its fd into non-blocking mode and registering it with
+  /// the reactor.
+  pub fn new(io: T) -> io::Result<Async<T>> {
+    set_nonblocking(io.as_raw_fd())?;
+    let source = Reactor::get().register(io.as_raw_fd());
+    Ok(Async {
+      source,
+      io: Some(io),
+    })
+  }
+
+  /// Borrow the wrapped value.
+  pub fn get_ref(&self) -> &T {
+    self.io.as_ref().unwrap()
+  }
+
+  /// Repeatedly attempt `op` (a non-blocking read-flavoured syscall), parking
+  /// the task on the source's read waker list whenever it reports `WouldBlock`.
+  pub async fn read_with<R>(
+    &self,
+    mut op: impl FnMut(&T) -> io::Result<R>,
+  ) -> io::Result<R> {
+    loop {
+      match op(self.get_ref()) {
+        Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+        result => return result,
+      }
+      self.readable().await;
+    }
+  }
+
+  /// As [`read_with`](Self::read_with) but for write-flavoured syscalls.
+  pub async fn write_with<R>(
+    &self,
+    mut op: impl FnMut(&T) -> io::Result<R>,
+  ) -> io::Result<R> {
+    loop {
+      match op(self.get_ref()) {
+        Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+        result => return result,
+      }
+      self.writable().await;
+    }
+  }
+
+  /// Resolve once the fd is readable, registering the task's waker meanwhile.
+  fn readable(&self) -> Readiness<'_, T> {
+    Readiness {
+      async_io: self,
+      write: false,
+      registered: false,
+    }
+  }
+
+  /// Resolve once the fd is writable.
+  fn writable(&self) -> Readiness<'_, T> {
+    Readiness {
+      async_io: self,
+      write: true,
+      registered: false,
+    }
+  }
+}
+
+impl<T: AsRawFd> Drop for Async<T> {
+  fn drop(&mut self) {
+    Reactor::get().deregister(&self.source);
+  }
+}
+
+/// A one-shot future that parks the current task until the wrapped fd becomes
+/// readable (or writable). It registers the waker with the source and re-arms
+/// the poller; the reactor thread completes it.
+struct Readiness<'a, T: AsRawFd> {
+  async_io: &'a Async<T>,
+  write: bool,
+  /// Set once the waker has been parked; the next poll completes.
+  registered: bool,
+}
+
+impl<T: AsRawFd> Future for Readiness<'_, T> {
+  type Output = ();
+
+  fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+    if self.registered {
+      return Poll::Ready(());
+    }
+    {
+      let mut source = self.async_io.source.lock().unwrap();
+      let wakers = if self.write {
+        &mut source.writers
+      } else {
+        &mut source.readers
+      };
+      if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+        wakers.push(cx.waker().clone());
+      }
+    }
+    Reactor::get().rearm(&self.async_io.source);
+    self.registered = true;
+    // We always yield once; the reactor wakes us when the fd is ready, and the
+    // caller retries the syscall. A spurious wakeup simply retries early.
+    Poll::Pending
+  }
+}
+
+/// Put a raw fd into non-blocking mode.
+fn set_nonblocking(fd: RawFd) -> io::Result<()> {
+  // SAFETY: `F_GETFL`/`F_SETFL` on a valid fd owned by the caller.
+  unsafe {
+    let flags = libc::fcntl(fd, libc::F_GETFL);
+    if flags < 0 {
+      return Err(io::Error::last_os_error());
+    }
+    if libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) < 0 {
+      return Err(io::Error::last_os_error());
+    }
+  }
+  Ok(())
+}
+
+// --- Concrete socket conveniences --------------------------------------------
+
+impl Async<TcpListener> {
+  /// Bind a non-blocking listener on `addr`.
+  pub fn bind(addr: &str) -> io::Result<Async<TcpListener>> {
+    Async::new(TcpListener::bind(addr)?)
+  }
+
+  /// Await an incoming connection, returning it wrapped for async use.
+  pub async fn accept(&self) -> io::Result<(Async<TcpStream>, std::net::SocketAddr)> {
+    let (stream, addr) = self
+      .read_with(|listener| listener.accept())
+      .await?;
+    Ok((Async::new(stream)?, addr))
+  }
+}
+
+impl Async<TcpStream> {
+  /// Await a read into `buf`, returning the number of bytes read.
+  pub async fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
+    self.read_with(|mut stream| stream.read(buf)).await
+  }
+
+  /// Await until all of `buf` has been written.
+  pub async fn write_all(&self, buf: &[u8]) -> io::Result<()> {
+    let mut written = 0;
+    while written < buf.len() {
+      written += self
+        .write_with(|mut stream| stream.write(&buf[written..]))
+        .await?;
+    }
+    Ok(())
+  }
+}
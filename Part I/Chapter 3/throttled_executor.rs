@@ -0,0 +1,485 @@
+/* A Throttling Executor for Low-Latency Wakeup Batching */
+
+// For a book about *low-latency* systems the interesting executor knob is not
+// raw throughput but tail latency under load. `gst-plugins-rs` layers a
+// throttling strategy on top of its smol-style runtime: instead of reacting to
+// every single I/O wakeup the instant it arrives, each worker processes ready
+// tasks in fixed time-quantum bursts, amortising syscall and context-switch
+// overhead when events arrive faster than they can be individually serviced.
+//
+// This module layers the same idea on the home-grown runtime. Each worker owns
+// a *per-thread* reactor (its own `Poller` plus a `Slab` of `Source`s) and a
+// configurable `throttling` quantum. The worker loop is:
+//
+//   1. drain and poll every currently-ready task;
+//   2. call the reactor's `poll` with a timeout equal to the time left in the
+//      current quantum, rather than returning to the scheduler after each
+//      event;
+//   3. dispatch the drained events — waking the tasks parked on each ready fd —
+//      so I/O that lands during the quantum is serviced together at the next
+//      tick.
+//
+// The tradeoff: a larger quantum batches more work per wakeup (higher
+// throughput, fewer syscalls) at the cost of holding ready events for up to one
+// quantum before dispatch (higher, but bounded, latency). A zero quantum
+// degrades to react-immediately behaviour. `udp_throttle_bench.rs` measures the
+// resulting tail latency at different quantum sizes.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::io::{self, ErrorKind};
+use std::os::fd::{AsRawFd, BorrowedFd, RawFd};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use polling::{Event, Events, Poller};
+use slab::Slab;
+
+type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Configures and spawns a [`Executor`].
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use crate::throttled_executor::ExecutorBuilder;
+/// let exec = ExecutorBuilder::new()
+///   .workers(4)
+///   .throttle(Duration::from_millis(2))
+///   .build();
+/// ```
+pub struct ExecutorBuilder {
+  workers: usize,
+  throttle: Duration,
+}
+
+impl Default for ExecutorBuilder {
+  fn default() -> Self {
+    ExecutorBuilder {
+      workers: 1,
+      throttle: Duration::ZERO,
+    }
+  }
+}
+
+impl ExecutorBuilder {
+  /// Start from the defaults: a single worker with no throttling (events are
+  /// dispatched as soon as they arrive).
+  pub fn new() -> ExecutorBuilder {
+    ExecutorBuilder::default()
+  }
+
+  /// Set the number of worker threads, each with its own reactor.
+  pub fn workers(mut self, workers: usize) -> ExecutorBuilder {
+    assert!(workers > 0, "an executor needs at least one worker");
+    self.workers = workers;
+    self
+  }
+
+  /// Set the per-worker time quantum. During a quantum a worker batches I/O
+  /// events instead of waking after each one. `Duration::ZERO` disables
+  /// throttling.
+  pub fn throttle(mut self, throttle: Duration) -> ExecutorBuilder {
+    self.throttle = throttle;
+    self
+  }
+
+  /// Spawn the worker threads and return the handle used to submit tasks.
+  pub fn build(self) -> Executor {
+    let workers: Vec<Arc<WorkerState>> = (0..self.workers)
+      .map(|_| {
+        Arc::new(WorkerState {
+          ready: Mutex::new(VecDeque::new()),
+          poller: Poller::new().expect("failed to create per-worker poller"),
+          sources: Mutex::new(Slab::new()),
+          throttle: self.throttle,
+          shutdown: AtomicBool::new(false),
+        })
+      })
+      .collect();
+
+    let next = Arc::new(AtomicUsize::new(0));
+    let handles = workers
+      .iter()
+      .enumerate()
+      .map(|(index, state)| {
+        let state = state.clone();
+        thread::Builder::new()
+          .name(format!("throttled-worker-{index}"))
+          .spawn(move || run_worker(state))
+          .expect("failed to spawn worker thread")
+      })
+      .collect();
+
+    Executor {
+      workers,
+      next,
+      handles,
+    }
+  }
+}
+
+/// A multi-threaded executor whose workers batch wakeups per time quantum.
+pub struct Executor {
+  workers: Vec<Arc<WorkerState>>,
+  next: Arc<AtomicUsize>,
+  handles: Vec<JoinHandle<()>>,
+}
+
+impl Executor {
+  /// Spawn a future onto the executor, assigning it to a worker round-robin.
+  /// Any [`Async`] the future creates registers with that same worker's
+  /// reactor, so its I/O is driven by the thread that polls it.
+  pub fn spawn<F>(&self, future: F)
+  where
+    F: Future<Output = ()> + Send + 'static,
+  {
+    let index = self.next.fetch_add(1, Ordering::Relaxed) % self.workers.len();
+    let state = self.workers[index].clone();
+    let task = Arc::new(Task {
+      future: Mutex::new(Some(Box::pin(future))),
+      worker: state.clone(),
+    });
+    state.schedule(task);
+  }
+}
+
+impl Drop for Executor {
+  fn drop(&mut self) {
+    // Signal shutdown via an explicit flag the workers check — we must not
+    // gate termination on the Arc refcount, since the handles below still
+    // hold a clone of every `WorkerState` while we join.
+    for state in &self.workers {
+      state.shutdown.store(true, Ordering::SeqCst);
+      let _ = state.poller.notify(); // Break the reactor out of `wait`.
+    }
+    for handle in self.handles.drain(..) {
+      let _ = handle.join();
+    }
+  }
+}
+
+/// Per-worker shared state: its ready queue, its reactor (poller + source
+/// registry), its quantum, and a shutdown flag.
+struct WorkerState {
+  ready: Mutex<VecDeque<Arc<Task>>>,
+  poller: Poller,
+  sources: Mutex<Slab<Arc<Mutex<Source>>>>,
+  throttle: Duration,
+  shutdown: AtomicBool,
+}
+
+impl WorkerState {
+  fn schedule(&self, task: Arc<Task>) {
+    self.ready.lock().unwrap().push_back(task);
+    // Interrupt the reactor so a freshly-scheduled task is picked up at the
+    // next tick even if no I/O event fires.
+    let _ = self.poller.notify();
+  }
+
+  /// Register `raw` with this worker's reactor and return its source handle.
+  fn register(&self, raw: RawFd) -> Arc<Mutex<Source>> {
+    let mut sources = self.sources.lock().unwrap();
+    let entry = sources.vacant_entry();
+    let key = entry.key();
+    let source = Arc::new(Mutex::new(Source {
+      raw,
+      key,
+      readers: Vec::new(),
+      writers: Vec::new(),
+    }));
+    // SAFETY: the fd stays registered until `deregister` runs in `Async::drop`,
+    // before the descriptor itself is closed.
+    unsafe {
+      self
+        .poller
+        .add(raw, Event::none(key))
+        .expect("failed to register fd with poller");
+    }
+    entry.insert(source.clone());
+    source
+  }
+
+  fn deregister(&self, source: &Arc<Mutex<Source>>) {
+    let (raw, key) = {
+      let source = source.lock().unwrap();
+      (source.raw, source.key)
+    };
+    self.sources.lock().unwrap().try_remove(key);
+    // SAFETY: `raw` is still open; `Async::drop` deregisters before closing.
+    let _ = self.poller.delete(unsafe { BorrowedFd::borrow_raw(raw) });
+  }
+
+  fn rearm(&self, source: &Mutex<Source>) {
+    let source = source.lock().unwrap();
+    // SAFETY: `raw` remains valid while the owning `Async` is alive.
+    let _ = self
+      .poller
+      .modify(unsafe { BorrowedFd::borrow_raw(source.raw) }, source.interest());
+  }
+}
+
+/// Per-fd state: the descriptor plus the wakers of tasks parked on it.
+struct Source {
+  raw: RawFd,
+  key: usize,
+  readers: Vec<Waker>,
+  writers: Vec<Waker>,
+}
+
+impl Source {
+  fn interest(&self) -> Event {
+    let mut event = Event::none(self.key);
+    event.readable = !self.readers.is_empty();
+    event.writable = !self.writers.is_empty();
+    event
+  }
+}
+
+struct Task {
+  future: Mutex<Option<BoxFuture>>,
+  worker: Arc<WorkerState>,
+}
+
+impl Task {
+  fn run(self: Arc<Self>) {
+    let mut slot = self.future.lock().unwrap();
+    let Some(mut future) = slot.take() else {
+      return;
+    };
+    let waker = task_waker(self.clone());
+    let mut cx = Context::from_waker(&waker);
+    if future.as_mut().poll(&mut cx).is_pending() {
+      *slot = Some(future);
+    }
+  }
+}
+
+thread_local! {
+  /// The worker running the current task, so `Async::new` can bind its fd to
+  /// the reactor that polls it.
+  static CURRENT_WORKER: RefCell<Option<Arc<WorkerState>>> = const { RefCell::new(None) };
+}
+
+/// The throttled worker loop. Each iteration drains the ready set, polls every
+/// task once, then collects reactor events until the quantum is used up,
+/// and finally dispatches the drained events so the parked tasks are queued for
+/// the next tick.
+fn run_worker(state: Arc<WorkerState>) {
+  CURRENT_WORKER.with(|c| *c.borrow_mut() = Some(state.clone()));
+  let mut events = Events::new();
+  loop {
+    let tick = Instant::now();
+
+    // 1. Snapshot and poll everything ready at the start of the quantum. Tasks
+    //    woken while we poll wait for the next tick by design.
+    let batch: Vec<Arc<Task>> = state.ready.lock().unwrap().drain(..).collect();
+    for task in batch {
+      task.run();
+    }
+
+    if state.shutdown.load(Ordering::SeqCst) {
+      return;
+    }
+
+    // 2. Wait for I/O for the remainder of the quantum. With throttling off we
+    //    block until the next event; otherwise we keep collecting events until
+    //    the quantum is used up, so they are all dispatched together below.
+    events.clear();
+    if state.throttle.is_zero() {
+      if state.poller.wait(&mut events, None).is_err() {
+        continue;
+      }
+    } else {
+      let deadline = tick + state.throttle;
+      loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if state.poller.wait(&mut events, Some(remaining)).is_err() || remaining.is_zero() {
+          break;
+        }
+      }
+    }
+
+    // 3. Dispatch the batch of events: wake every task parked on a ready fd.
+    //    Their wakers push them back onto `ready` for the next tick.
+    for event in events.iter() {
+      let source = state.sources.lock().unwrap().get(event.key).cloned();
+      let Some(source) = source else { continue };
+      let mut source = source.lock().unwrap();
+      if event.readable {
+        source.readers.drain(..).for_each(Waker::wake);
+      }
+      if event.writable {
+        source.writers.drain(..).for_each(Waker::wake);
+      }
+    }
+  }
+}
+
+// --- `Async<T>` bound to the current worker's reactor ------------------------
+
+/// Makes a std I/O type awaitable on the throttling executor. Created from
+/// within a spawned task, it registers with the reactor of the worker that is
+/// running that task.
+pub struct Async<T: AsRawFd> {
+  worker: Arc<WorkerState>,
+  source: Arc<Mutex<Source>>,
+  io: Option<T>,
+}
+
+impl<T: AsRawFd> Async<T> {
+  /// Wrap `io`, set its fd non-blocking, and register it with the current
+  /// worker's reactor.
+  ///
+  /// # Panics
+  ///
+  /// Panics if called outside of a task running on this executor.
+  pub fn new(io: T) -> io::Result<Async<T>> {
+    let worker = CURRENT_WORKER.with(|c| {
+      c.borrow()
+        .as_ref()
+        .expect("`Async::new` called outside of an executor task")
+        .clone()
+    });
+    set_nonblocking(io.as_raw_fd())?;
+    let source = worker.register(io.as_raw_fd());
+    Ok(Async {
+      worker,
+      source,
+      io: Some(io),
+    })
+  }
+
+  /// Borrow the wrapped value.
+  pub fn get_ref(&self) -> &T {
+    self.io.as_ref().unwrap()
+  }
+
+  /// Attempt a read-flavoured syscall, parking on the source's read wakers when
+  /// it reports `WouldBlock`.
+  pub async fn read_with<R>(
+    &self,
+    mut op: impl FnMut(&T) -> io::Result<R>,
+  ) -> io::Result<R> {
+    loop {
+      match op(self.get_ref()) {
+        Err(err) if err.kind() == ErrorKind::WouldBlock => {}
+        result => return result,
+      }
+      Readiness {
+        async_io: self,
+        write: false,
+        registered: false,
+      }
+      .await;
+    }
+  }
+
+  /// As [`read_with`](Self::read_with) but for write-flavoured syscalls.
+  pub async fn write_with<R>(
+    &self,
+    mut op: impl FnMut(&T) -> io::Result<R>,
+  ) -> io::Result<R> {
+    loop {
+      match op(self.get_ref()) {
+        Err(err) if err.kind() == ErrorKind::WouldBlock => {}
+        result => return result,
+      }
+      Readiness {
+        async_io: self,
+        write: true,
+        registered: false,
+      }
+      .await;
+    }
+  }
+}
+
+impl<T: AsRawFd> Drop for Async<T> {
+  fn drop(&mut self) {
+    self.worker.deregister(&self.source);
+  }
+}
+
+/// A one-shot future that parks the current task until its fd is ready,
+/// registering the waker with the source and re-arming the poller.
+struct Readiness<'a, T: AsRawFd> {
+  async_io: &'a Async<T>,
+  write: bool,
+  /// Set once the waker has been parked; the next poll completes.
+  registered: bool,
+}
+
+impl<T: AsRawFd> Future for Readiness<'_, T> {
+  type Output = ();
+
+  fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+    if self.registered {
+      return Poll::Ready(());
+    }
+    {
+      let mut source = self.async_io.source.lock().unwrap();
+      let wakers = if self.write {
+        &mut source.writers
+      } else {
+        &mut source.readers
+      };
+      if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+        wakers.push(cx.waker().clone());
+      }
+    }
+    self.async_io.worker.rearm(&self.async_io.source);
+    self.registered = true;
+    Poll::Pending
+  }
+}
+
+/// Put a raw fd into non-blocking mode.
+fn set_nonblocking(fd: RawFd) -> io::Result<()> {
+  // SAFETY: `F_GETFL`/`F_SETFL` on a valid fd owned by the caller.
+  unsafe {
+    let flags = libc::fcntl(fd, libc::F_GETFL);
+    if flags < 0 {
+      return Err(io::Error::last_os_error());
+    }
+    if libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) < 0 {
+      return Err(io::Error::last_os_error());
+    }
+  }
+  Ok(())
+}
+
+// --- Task waker (schedules back onto the owning worker) ----------------------
+
+fn task_waker(task: Arc<Task>) -> Waker {
+  unsafe { Waker::from_raw(raw_waker(task)) }
+}
+
+fn raw_waker(task: Arc<Task>) -> RawWaker {
+  RawWaker::new(Arc::into_raw(task) as *const (), &VTABLE)
+}
+
+static VTABLE: RawWakerVTable = RawWakerVTable::new(
+  |ptr| unsafe {
+    let task = Arc::from_raw(ptr as *const Task);
+    let clone = task.clone();
+    std::mem::forget(task);
+    raw_waker(clone)
+  },
+  |ptr| unsafe {
+    let task = Arc::from_raw(ptr as *const Task);
+    task.worker.clone().schedule(task);
+  },
+  |ptr| unsafe {
+    let task = Arc::from_raw(ptr as *const Task);
+    task.worker.clone().schedule(task.clone());
+    std::mem::forget(task);
+  },
+  |ptr| unsafe {
+    drop(Arc::from_raw(ptr as *const Task));
+  },
+);
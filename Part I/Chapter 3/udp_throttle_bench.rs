@@ -0,0 +1,146 @@
+/* Tail Latency vs. Quantum Size for a Throttled UDP Receiver */
+
+// A measurable demonstration of the throttling tradeoff introduced in
+// `throttled_executor.rs`. A sender blasts timestamped datagrams at a
+// non-blocking UDP socket; the receiver runs the same quantum-batched loop a
+// worker uses (drain ready data, then `poll` for the remainder of the quantum)
+// at a range of quantum sizes. For each we report throughput and the p50/p99
+// receive latency so the tail-latency behaviour is visible:
+//
+//   * a zero / tiny quantum wakes per datagram — lowest latency, most syscalls;
+//   * a larger quantum amortises the syscalls — higher throughput, but a
+//     datagram can wait up to one quantum before it is read, which shows up in
+//     the p99.
+//
+// This mirrors the worker loop in `throttled_executor::run_worker`; it drives
+// the socket directly so the measurement stays self-contained.
+
+use std::io::ErrorKind;
+use std::net::UdpSocket;
+use std::os::fd::AsRawFd;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use polling::{Event, Events, Poller};
+
+const DATAGRAMS: u64 = 100_000;
+/// Hard cap on a single run, so the demo always returns even though UDP
+/// loopback silently drops datagrams once the receiver sleeps a full quantum.
+const DEADLINE: Duration = Duration::from_secs(10);
+
+/// Nanoseconds since a shared epoch, serialised little-endian into a datagram.
+fn now_ns(epoch: Instant) -> u64 {
+  epoch.elapsed().as_nanos() as u64
+}
+
+/// Run the receiver for one quantum setting, returning (throughput, p50, p99).
+fn measure(quantum: Duration) -> (f64, Duration, Duration) {
+  let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+  let addr = server.local_addr().unwrap();
+  server.set_nonblocking(true).unwrap();
+
+  let epoch = Instant::now();
+
+  // How many datagrams the sender actually put on the wire, and whether it has
+  // finished. The receiver watches both so it never waits for datagrams that
+  // were dropped (or never sent).
+  let sent = Arc::new(AtomicU64::new(0));
+  let finished = Arc::new(AtomicBool::new(false));
+
+  let sender = {
+    let sent = sent.clone();
+    let finished = finished.clone();
+    thread::spawn(move || {
+      let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+      for _ in 0..DATAGRAMS {
+        let stamp = now_ns(epoch).to_le_bytes();
+        if client.send_to(&stamp, addr).is_err() {
+          break;
+        }
+        sent.fetch_add(1, Ordering::Relaxed);
+      }
+      finished.store(true, Ordering::Relaxed);
+    })
+  };
+
+  let poller = Poller::new().unwrap();
+  // SAFETY: the socket outlives its registration in this function.
+  unsafe {
+    poller.add(server.as_raw_fd(), Event::readable(0)).unwrap();
+  }
+
+  let mut events = Events::new();
+  let mut latencies = Vec::with_capacity(DATAGRAMS as usize);
+  let mut buf = [0u8; 8];
+  let start = Instant::now();
+
+  loop {
+    let tick = Instant::now();
+
+    // Drain everything currently readable (the batch for this quantum).
+    loop {
+      match server.recv(&mut buf) {
+        Ok(n) if n == 8 => {
+          let stamp = u64::from_le_bytes(buf);
+          latencies.push(now_ns(epoch).saturating_sub(stamp));
+        }
+        Ok(_) => {}
+        Err(ref e) if e.kind() == ErrorKind::WouldBlock => break,
+        Err(_) => break,
+      }
+    }
+
+    // Stop once we have caught up with everything the sender managed to send
+    // (accounting for loopback drops), or if we blow the deadline.
+    let done_sending = finished.load(Ordering::Relaxed);
+    if done_sending && latencies.len() as u64 >= sent.load(Ordering::Relaxed) {
+      break;
+    }
+    if start.elapsed() >= DEADLINE {
+      break;
+    }
+
+    // Wait out the rest of the quantum for more datagrams to accumulate.
+    events.clear();
+    if quantum.is_zero() {
+      let _ = poller.wait(&mut events, Some(Duration::from_millis(1)));
+    } else {
+      let deadline = tick + quantum;
+      loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let _ = poller.wait(&mut events, Some(remaining));
+        if remaining.is_zero() {
+          break;
+        }
+      }
+    }
+    // `polling` is oneshot; re-arm for the next tick.
+    let _ = poller.modify(&server, Event::readable(0));
+  }
+
+  let elapsed = start.elapsed();
+  sender.join().unwrap();
+
+  if latencies.is_empty() {
+    return (0.0, Duration::ZERO, Duration::ZERO);
+  }
+  latencies.sort_unstable();
+  let p = |q: f64| Duration::from_nanos(latencies[(latencies.len() as f64 * q) as usize]);
+  let throughput = latencies.len() as f64 / elapsed.as_secs_f64();
+  (throughput, p(0.50), p(0.99))
+}
+
+fn main() {
+  println!("{:>10}  {:>12}  {:>10}  {:>10}", "quantum", "datagrams/s", "p50", "p99");
+  for quantum in [
+    Duration::ZERO,
+    Duration::from_micros(500),
+    Duration::from_millis(2),
+    Duration::from_millis(8),
+  ] {
+    let (throughput, p50, p99) = measure(quantum);
+    println!("{quantum:>10?}  {throughput:>12.0}  {p50:>10?}  {p99:>10?}");
+  }
+}
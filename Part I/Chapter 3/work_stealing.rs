@@ -0,0 +1,268 @@
+/* A Multi-Threaded Work-Stealing Scheduler */
+
+// The concurrency snippets in this chunk only ever `thread::spawn` raw
+// closures. Production runtimes (Tokio, Rayon, Go's scheduler) instead keep a
+// fixed pool of worker threads and balance work between them with
+// *work-stealing* deques. This module builds one on top of `crossbeam-deque`,
+// whose `Worker`/`Stealer`/`Injector` are Chase–Lev deques.
+//
+// The design mirrors those runtimes:
+//
+//   * Each worker owns a local LIFO `Worker<Task>` deque. It `push`es and
+//     `pop`s on *its own* end only — the hot path is wait-free and
+//     cache-friendly (the most recently produced work is reused first).
+//   * A shared `Injector<Task>` holds work submitted from outside the pool.
+//   * When its local deque is empty a worker first drains a batch from the
+//     injector, then, failing that, steals from a sibling's `Stealer` — which
+//     only ever touches the *far* end of that sibling's deque.
+//   * An atomic counter tracks outstanding tasks so the pool knows when every
+//     submitted closure has finished.
+//
+// Invariants worth remembering (and the reason the hot path needs no locks):
+//
+//   1. Only the owning thread calls `push`/`pop` on its `Worker`; siblings and
+//      the pool only ever hold `Stealer`s.
+//   2. `Stealer`s take from the opposite end, so a steal never races the
+//      owner's LIFO operations destructively.
+//   3. `pending` is incremented before a task is made visible and decremented
+//      only after it has run, so reaching zero means the pool is truly idle.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crossbeam_deque::{Injector, Stealer, Worker};
+use crossbeam_utils::Backoff;
+
+/// A boxed unit of work. Closures are erased to a uniform type so the deques
+/// can hold heterogeneous jobs.
+type Task = Box<dyn FnOnce() + Send + 'static>;
+
+/// State shared by every worker thread and the pool handle.
+struct Shared {
+  /// Global queue for work submitted via [`Pool::spawn`].
+  injector: Injector<Task>,
+  /// One stealer per worker, so any worker can steal from any sibling.
+  stealers: Vec<Stealer<Task>>,
+  /// Number of tasks submitted but not yet finished.
+  pending: AtomicUsize,
+  /// Set during shutdown to let parked workers exit.
+  shutdown: AtomicBool,
+  /// Park/unpark coordination for idle workers and `join` waiters.
+  idle: Mutex<()>,
+  signal: Condvar,
+}
+
+/// A fixed-size pool of work-stealing worker threads.
+pub struct Pool {
+  shared: Arc<Shared>,
+  workers: Vec<JoinHandle<()>>,
+}
+
+impl Pool {
+  /// Create a pool with `threads` workers. Each worker gets its own local
+  /// deque; the pool keeps a `Stealer` for every worker.
+  pub fn new(threads: usize) -> Pool {
+    assert!(threads > 0, "a pool needs at least one worker");
+
+    let locals: Vec<Worker<Task>> = (0..threads).map(|_| Worker::new_lifo()).collect();
+    let stealers = locals.iter().map(Worker::stealer).collect();
+
+    let shared = Arc::new(Shared {
+      injector: Injector::new(),
+      stealers,
+      pending: AtomicUsize::new(0),
+      shutdown: AtomicBool::new(false),
+      idle: Mutex::new(()),
+      signal: Condvar::new(),
+    });
+
+    let workers = locals
+      .into_iter()
+      .enumerate()
+      .map(|(index, local)| {
+        let shared = shared.clone();
+        thread::Builder::new()
+          .name(format!("worker-{index}"))
+          .spawn(move || run_worker(index, local, shared))
+          .expect("failed to spawn worker thread")
+      })
+      .collect();
+
+    Pool { shared, workers }
+  }
+
+  /// Submit a closure to the pool. It is pushed onto the global injector and a
+  /// parked worker is woken to pick it up.
+  pub fn spawn<F>(&self, task: F)
+  where
+    F: FnOnce() + Send + 'static,
+  {
+    self.shared.pending.fetch_add(1, Ordering::SeqCst);
+    self.shared.injector.push(Box::new(task));
+    // Wake one worker; if all are busy the wakeup is simply absorbed.
+    let _guard = self.shared.idle.lock().unwrap();
+    self.shared.signal.notify_one();
+  }
+
+  /// Block until every submitted task has finished running.
+  pub fn join(&self) {
+    let mut guard = self.shared.idle.lock().unwrap();
+    while self.shared.pending.load(Ordering::SeqCst) != 0 {
+      guard = self.shared.signal.wait(guard).unwrap();
+    }
+  }
+}
+
+impl Drop for Pool {
+  fn drop(&mut self) {
+    self.shared.shutdown.store(true, Ordering::SeqCst);
+    {
+      let _guard = self.shared.idle.lock().unwrap();
+      self.shared.signal.notify_all();
+    }
+    for handle in self.workers.drain(..) {
+      let _ = handle.join();
+    }
+  }
+}
+
+/// The body of a single worker thread: find a task (local, then injector, then
+/// siblings), run it, and park when there is nothing to do.
+fn run_worker(index: usize, local: Worker<Task>, shared: Arc<Shared>) {
+  // Reset on every successful find; grows the wait while the search keeps
+  // coming up empty so a worker with no work stops burning a core.
+  let backoff = Backoff::new();
+  loop {
+    match find_task(index, &local, &shared) {
+      Some(task) => {
+        backoff.reset();
+        task();
+        // Finishing a task may have made the pool idle; tell `join`.
+        if shared.pending.fetch_sub(1, Ordering::SeqCst) == 1 {
+          let _guard = shared.idle.lock().unwrap();
+          shared.signal.notify_all();
+        }
+      }
+      None => {
+        if shared.shutdown.load(Ordering::SeqCst) {
+          return;
+        }
+        if shared.pending.load(Ordering::SeqCst) == 0 {
+          // The whole pool is drained: park until a submit or shutdown wakes
+          // us. We recheck under the lock to avoid a lost wakeup.
+          backoff.reset();
+          let guard = shared.idle.lock().unwrap();
+          if shared.pending.load(Ordering::SeqCst) == 0
+            && !shared.shutdown.load(Ordering::SeqCst)
+          {
+            let _unused = shared.signal.wait(guard).unwrap();
+          }
+        } else {
+          // Work exists but this worker's three-tier search lost the race for
+          // it; back off briefly instead of hot-spinning at 100% CPU.
+          backoff.snooze();
+        }
+      }
+    }
+  }
+}
+
+/// The three-tier task search: own deque, then a batch from the injector, then
+/// a steal from a sibling.
+fn find_task(index: usize, local: &Worker<Task>, shared: &Shared) -> Option<Task> {
+  // 1. Our own LIFO end — the wait-free fast path.
+  if let Some(task) = local.pop() {
+    return Some(task);
+  }
+
+  loop {
+    // 2. Pull a batch from the global injector into our local deque.
+    match shared.injector.steal_batch_and_pop(local) {
+      crossbeam_deque::Steal::Success(task) => return Some(task),
+      crossbeam_deque::Steal::Retry => continue,
+      crossbeam_deque::Steal::Empty => {}
+    }
+
+    // 3. Steal from a sibling's far end. Skip our own stealer.
+    for (i, stealer) in shared.stealers.iter().enumerate() {
+      if i == index {
+        continue;
+      }
+      match stealer.steal() {
+        crossbeam_deque::Steal::Success(task) => return Some(task),
+        crossbeam_deque::Steal::Retry => continue,
+        crossbeam_deque::Steal::Empty => {}
+      }
+    }
+
+    return None;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::sync::atomic::AtomicUsize;
+  use std::time::{Duration, Instant};
+
+  /// `pending` reaching zero ⇒ idle: every spawned task runs exactly once and
+  /// `join` observes the full count.
+  #[test]
+  fn join_observes_every_task() {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let pool = Pool::new(4);
+    const TASKS: usize = 10_000;
+
+    for _ in 0..TASKS {
+      let counter = counter.clone();
+      pool.spawn(move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+      });
+    }
+    pool.join();
+
+    assert_eq!(counter.load(Ordering::SeqCst), TASKS);
+  }
+
+  /// `join` returns promptly once work is done rather than spinning or hanging.
+  #[test]
+  fn join_returns_when_idle() {
+    let pool = Pool::new(2);
+    pool.spawn(|| {});
+    let start = Instant::now();
+    pool.join();
+    assert!(start.elapsed() < Duration::from_secs(5));
+  }
+
+  /// Work submitted from many threads is still fully accounted for — exercises
+  /// the injector and the stealers under contention.
+  #[test]
+  fn accounts_for_work_from_many_producers() {
+    let pool = Arc::new(Pool::new(4));
+    let counter = Arc::new(AtomicUsize::new(0));
+    const PRODUCERS: usize = 8;
+    const PER_PRODUCER: usize = 1_000;
+
+    let producers: Vec<_> = (0..PRODUCERS)
+      .map(|_| {
+        let pool = pool.clone();
+        let counter = counter.clone();
+        thread::spawn(move || {
+          for _ in 0..PER_PRODUCER {
+            let counter = counter.clone();
+            pool.spawn(move || {
+              counter.fetch_add(1, Ordering::SeqCst);
+            });
+          }
+        })
+      })
+      .collect();
+    for producer in producers {
+      producer.join().unwrap();
+    }
+    pool.join();
+
+    assert_eq!(counter.load(Ordering::SeqCst), PRODUCERS * PER_PRODUCER);
+  }
+}